@@ -1,29 +1,207 @@
 use rustler::NifResult;
 use oxc_allocator::Allocator;
+use oxc_ast::ast::{Statement, TSType, TSTypeName};
+use oxc_ast_visit::{walk, Visit};
+use oxc_diagnostics::OxcDiagnostic;
 use oxc_parser::Parser;
-use oxc_semantic::SemanticBuilder;
-use oxc_span::SourceType;
+use oxc_semantic::{SemanticBuilder, SymbolFlags};
+use oxc_span::{GetSpan, SourceType, Span};
+use rayon::prelude::*;
+use std::collections::HashMap;
 
+/// One parser or semantic diagnostic translated into line/column form for Elixir.
+#[derive(rustler::NifMap)]
+struct Diagnostic {
+    message: String,
+    severity: String,
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+    code: String,
+}
+
+/// Byte offset of the start of each line in `source`, so a span's byte offset
+/// can be translated into a 1-based (line, column) pair without rescanning.
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Translates a byte offset into a 1-based (line, column) pair using a
+/// precomputed `line_starts` table.
+fn offset_to_line_col(starts: &[usize], offset: usize) -> (u32, u32) {
+    let line = starts.partition_point(|&start| start <= offset).saturating_sub(1);
+    let column = offset - starts[line];
+    (line as u32 + 1, column as u32 + 1)
+}
+
+/// Converts oxc's miette-style diagnostics into our flat `Diagnostic` shape,
+/// shifting each span back by `prefix_len` so columns land on the caller's
+/// original source rather than the synthetic `type __ValidationType = ` wrapper.
+fn to_diagnostics(errors: &[OxcDiagnostic], starts: &[usize], prefix_len: usize) -> Vec<Diagnostic> {
+    errors
+        .iter()
+        .map(|error| {
+            let label = error.labels.as_slice().first().cloned();
+            let (start_offset, end_offset) = label
+                .map(|l| (l.offset() as usize, (l.offset() + l.len()) as usize))
+                .unwrap_or((0, 0));
+            let start_offset = start_offset.saturating_sub(prefix_len);
+            let end_offset = end_offset.saturating_sub(prefix_len);
+            let (start_line, start_column) = offset_to_line_col(starts, start_offset);
+            let (end_line, end_column) = offset_to_line_col(starts, end_offset);
+
+            Diagnostic {
+                message: error.message.to_string(),
+                severity: format!("{:?}", error.severity).to_lowercase(),
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+                code: error.code.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Like `validate/1`, but returns diagnostics as structured maps (`:message`,
+/// `:severity`, `:start_line`, `:start_column`, `:end_line`, `:end_column`,
+/// `:code`) instead of one concatenated string. If the parser reports errors,
+/// only those are returned and semantic analysis is not run; otherwise the
+/// semantic diagnostics are returned, minus module-resolution errors.
 #[rustler::nif]
-fn validate(typescript_code: String) -> NifResult<Result<String, String>> {
-    // Create allocator for AST
+fn validate_detailed(typescript_code: String) -> NifResult<Result<Vec<Diagnostic>, Vec<Diagnostic>>> {
     let allocator = Allocator::default();
 
-    // Configure TypeScript source type
-    // Parse as a TypeScript module (.ts) to handle imports and exports
     let source_type = SourceType::from_path("check.ts")
         .unwrap_or_else(|_| SourceType::default().with_typescript(true).with_module(true));
 
-    // If the input doesn't look like a complete statement (no 'export', 'type', 'interface'),
-    // wrap it as a type alias to validate the type expression
-    let code_to_validate = if typescript_code.trim_start().starts_with("export")
+    let is_wrapped = !(typescript_code.trim_start().starts_with("export")
         || typescript_code.trim_start().starts_with("type ")
         || typescript_code.trim_start().starts_with("interface ")
-        || typescript_code.trim_start().starts_with("declare ") {
-        typescript_code.clone()
+        || typescript_code.trim_start().starts_with("declare "));
+
+    let prefix = "type __ValidationType = ";
+    let code_to_validate = if is_wrapped {
+        format!("{}{};", prefix, typescript_code)
     } else {
+        typescript_code.clone()
+    };
+    let prefix_len = if is_wrapped { prefix.len() } else { 0 };
+    let starts = line_starts(&typescript_code);
+
+    let parser_return = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        Parser::new(&allocator, &code_to_validate, source_type).parse()
+    }));
+
+    let parser_return = match parser_return {
+        Ok(result) => result,
+        Err(_) => {
+            return Ok(Err(vec![Diagnostic {
+                message: "TypeScript parser encountered an unrecoverable error".to_string(),
+                severity: "error".to_string(),
+                start_line: 1,
+                start_column: 1,
+                end_line: 1,
+                end_column: 1,
+                code: String::new(),
+            }]));
+        }
+    };
+
+    if parser_return.panicked || !parser_return.diagnostics.is_empty() {
+        return Ok(Err(to_diagnostics(&parser_return.diagnostics, &starts, prefix_len)));
+    }
+
+    let semantic_return = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        SemanticBuilder::new()
+            .with_check_syntax_error(true)
+            .build(&parser_return.program)
+    }));
+
+    let semantic_return = match semantic_return {
+        Ok(result) => result,
+        Err(_) => return Ok(Ok(Vec::new())),
+    };
+
+    if !semantic_return.diagnostics.is_empty() {
+        let syntax_errors: Vec<OxcDiagnostic> = semantic_return
+            .diagnostics
+            .into_iter()
+            .filter(|e| {
+                let msg = format!("{}", e);
+                !msg.contains("Cannot find") && !msg.contains("module")
+            })
+            .collect();
+
+        if !syntax_errors.is_empty() {
+            return Ok(Err(to_diagnostics(&syntax_errors, &starts, prefix_len)));
+        }
+    }
+
+    Ok(Ok(Vec::new()))
+}
+
+/// Options accepted by `validate/2`. All fields are optional; omitted fields
+/// fall back to the historical `validate/1` behavior (TypeScript, module mode,
+/// no JSX).
+#[derive(rustler::NifMap, Default, Clone)]
+struct ValidateOptions {
+    jsx: Option<bool>,
+    typescript: Option<bool>,
+    module: Option<bool>,
+    dts: Option<bool>,
+    filename: Option<String>,
+}
+
+/// Builds a `SourceType` for the given options. A `filename` that
+/// `SourceType::from_path` recognizes takes precedence and the explicit flags
+/// are then ignored; otherwise the source type is built from the flags.
+fn build_source_type(options: &ValidateOptions) -> SourceType {
+    if let Some(filename) = &options.filename {
+        if let Ok(source_type) = SourceType::from_path(filename) {
+            return source_type;
+        }
+    }
+
+    SourceType::default()
+        .with_typescript(options.typescript.unwrap_or(true))
+        .with_typescript_definition(options.dts.unwrap_or(false))
+        .with_jsx(options.jsx.unwrap_or(false))
+        .with_module(options.module.unwrap_or(true))
+}
+
+/// A snippet already looks like a complete statement (as opposed to a bare
+/// type expression) if it starts with one of these keywords, or is itself a
+/// `.d.ts`-style ambient declaration file.
+fn looks_like_statement(typescript_code: &str, is_dts: bool) -> bool {
+    is_dts
+        || typescript_code.trim_start().starts_with("export")
+        || typescript_code.trim_start().starts_with("type ")
+        || typescript_code.trim_start().starts_with("interface ")
+        || typescript_code.trim_start().starts_with("declare ")
+}
+
+/// Shared implementation behind `validate/1` and `validate/2`.
+fn validate_with_options(typescript_code: String, options: ValidateOptions) -> Result<String, String> {
+    // Create allocator for AST
+    let allocator = Allocator::default();
+
+    let source_type = build_source_type(&options);
+    let is_dts = source_type.is_typescript_definition();
+
+    // If the input doesn't look like a complete statement (no 'export', 'type', 'interface', 'declare'),
+    // wrap it as a type alias to validate the type expression. Only do this for
+    // TypeScript input that isn't already an ambient declaration file.
+    let should_wrap = source_type.is_typescript()
+        && !looks_like_statement(&typescript_code, is_dts);
+    let code_to_validate = if should_wrap {
         // Wrap bare type expression in a type alias
         format!("type __ValidationType = {};", typescript_code)
+    } else {
+        typescript_code.clone()
     };
 
     // Parse the TypeScript code
@@ -37,23 +215,23 @@ fn validate(typescript_code: String) -> NifResult<Result<String, String>> {
         Err(_) => {
             // Parser panicked - likely a bug in oxc or unsupported syntax
             // Fall back to basic validation
-            return Ok(Err("TypeScript parser encountered an unrecoverable error".to_string()));
+            return Err("TypeScript parser encountered an unrecoverable error".to_string());
         }
     };
 
     // Check for parser panic (unrecoverable error)
     if parser_return.panicked {
-        return Ok(Err("TypeScript parser encountered an unrecoverable error".to_string()));
+        return Err("TypeScript parser encountered an unrecoverable error".to_string());
     }
 
     // Check for parser syntax errors first
-    if !parser_return.errors.is_empty() {
-        let errors: Vec<String> = parser_return.errors
+    if !parser_return.diagnostics.is_empty() {
+        let errors: Vec<String> = parser_return.diagnostics
             .iter()
             .map(|e| format!("{}", e))
             .collect();
 
-        return Ok(Err(format!("TypeScript syntax error: {}", errors.join("; "))));
+        return Err(format!("TypeScript syntax error: {}", errors.join("; ")));
     }
 
     // Run semantic analysis with strict syntax error checking enabled
@@ -75,15 +253,15 @@ fn validate(typescript_code: String) -> NifResult<Result<String, String>> {
         Err(_) => {
             // Semantic analysis failed (likely module resolution)
             // But parser succeeded, so the syntax is valid
-            return Ok(Ok(typescript_code));
+            return Ok(typescript_code);
         }
     };
 
     // Only report semantic errors if there are actual syntax issues
     // (not just missing module resolution)
-    if !semantic_return.errors.is_empty() {
+    if !semantic_return.diagnostics.is_empty() {
         // Filter out module resolution errors since we're validating isolated files
-        let syntax_errors: Vec<String> = semantic_return.errors
+        let syntax_errors: Vec<String> = semantic_return.diagnostics
             .iter()
             .filter(|e| {
                 let msg = format!("{}", e);
@@ -94,12 +272,643 @@ fn validate(typescript_code: String) -> NifResult<Result<String, String>> {
             .collect();
 
         if !syntax_errors.is_empty() {
-            return Ok(Err(format!("TypeScript syntax error: {}", syntax_errors.join("; "))));
+            return Err(format!("TypeScript syntax error: {}", syntax_errors.join("; ")));
         }
     }
 
     // Validation successful - return original code
-    Ok(Ok(typescript_code))
+    Ok(typescript_code)
+}
+
+/// Result of `validate_recover/1`: every diagnostic the parser and semantic
+/// builder produced in one pass, plus whether a usable AST was still recovered.
+#[derive(rustler::NifMap)]
+struct RecoverResult {
+    recovered: bool,
+    panicked: bool,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Deduplicates diagnostics that the parser and semantic builder both reported
+/// for the same span (e.g. a syntax error the semantic pass also flags),
+/// preserving the order they were first seen in.
+fn dedup_diagnostics(diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    let mut seen = std::collections::HashSet::new();
+    diagnostics
+        .into_iter()
+        .filter(|d| {
+            let key = (
+                d.message.clone(),
+                d.start_line,
+                d.start_column,
+                d.end_line,
+                d.end_column,
+            );
+            seen.insert(key)
+        })
+        .collect()
+}
+
+/// Parses `typescript_code` without stopping at the first error: unlike
+/// `validate/1`, this always runs `SemanticBuilder` over whatever the parser
+/// recovered and returns every parser and semantic diagnostic in one pass, so
+/// editor/LSP-style callers can show all problems at once.
+#[rustler::nif]
+fn validate_recover(typescript_code: String) -> NifResult<RecoverResult> {
+    let allocator = Allocator::default();
+
+    let source_type = SourceType::from_path("check.ts")
+        .unwrap_or_else(|_| SourceType::default().with_typescript(true).with_module(true));
+
+    let is_wrapped = !looks_like_statement(&typescript_code, false);
+    let prefix = "type __ValidationType = ";
+    let code_to_validate = if is_wrapped {
+        format!("{}{};", prefix, typescript_code)
+    } else {
+        typescript_code.clone()
+    };
+    let prefix_len = if is_wrapped { prefix.len() } else { 0 };
+    let starts = line_starts(&typescript_code);
+
+    let parser_return = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        Parser::new(&allocator, &code_to_validate, source_type).parse()
+    }));
+
+    let parser_return = match parser_return {
+        Ok(result) => result,
+        Err(_) => {
+            return Ok(RecoverResult {
+                recovered: false,
+                panicked: true,
+                diagnostics: Vec::new(),
+            });
+        }
+    };
+
+    if parser_return.panicked {
+        return Ok(RecoverResult {
+            recovered: false,
+            panicked: true,
+            diagnostics: to_diagnostics(&parser_return.diagnostics, &starts, prefix_len),
+        });
+    }
+
+    let mut diagnostics = to_diagnostics(&parser_return.diagnostics, &starts, prefix_len);
+    let recovered = !parser_return.program.body.is_empty();
+
+    // Always run semantic analysis, even if the parser already reported
+    // errors, so callers get every diagnostic in one pass rather than
+    // one-at-a-time.
+    let semantic_return = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        SemanticBuilder::new()
+            .with_check_syntax_error(true)
+            .build(&parser_return.program)
+    }));
+
+    if let Ok(semantic_return) = semantic_return {
+        let syntax_errors: Vec<OxcDiagnostic> = semantic_return
+            .diagnostics
+            .into_iter()
+            .filter(|e| {
+                let msg = format!("{}", e);
+                !msg.contains("Cannot find") && !msg.contains("module")
+            })
+            .collect();
+        diagnostics.extend(to_diagnostics(&syntax_errors, &starts, prefix_len));
+    }
+
+    Ok(RecoverResult {
+        recovered,
+        panicked: false,
+        diagnostics: dedup_diagnostics(diagnostics),
+    })
+}
+
+#[rustler::nif]
+fn validate(typescript_code: String) -> NifResult<Result<String, String>> {
+    Ok(validate_with_options(typescript_code, ValidateOptions::default()))
+}
+
+/// Like `validate/1`, but lets callers pick the parser dialect explicitly
+/// instead of assuming a TypeScript module: JSX/TSX components, `.d.ts`
+/// ambient declarations, and script-mode snippets.
+#[rustler::nif(name = "validate")]
+fn validate2(typescript_code: String, options: ValidateOptions) -> NifResult<Result<String, String>> {
+    Ok(validate_with_options(typescript_code, options))
+}
+
+/// Validates many snippets in one round-trip, running the parses across a
+/// rayon thread pool on a dirty CPU scheduler so a large batch (e.g. a whole
+/// schema) doesn't block a normal BEAM scheduler thread per item. Each item
+/// gets its own `Allocator` (oxc ASTs are bump-allocated per parse), so the
+/// batch is embarrassingly parallel; per-item panic-catching and bare-type
+/// wrapping behave exactly as in `validate/1`.
+#[rustler::nif(schedule = "DirtyCpu")]
+fn validate_batch(typescript_codes: Vec<String>) -> NifResult<Vec<Result<String, String>>> {
+    let results = typescript_codes
+        .into_par_iter()
+        .map(|code| validate_with_options(code, ValidateOptions::default()))
+        .collect();
+    Ok(results)
+}
+
+// --- lint/1: in-file semantic findings ------------------------------------
+
+/// A lint finding, tagged with the rule that produced it so callers can
+/// filter or group by category.
+#[derive(rustler::NifMap)]
+struct LintDiagnostic {
+    message: String,
+    severity: String,
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+    rule: String,
+}
+
+fn with_rule(diagnostic: Diagnostic, rule: &str) -> LintDiagnostic {
+    LintDiagnostic {
+        message: diagnostic.message,
+        severity: diagnostic.severity,
+        start_line: diagnostic.start_line,
+        start_column: diagnostic.start_column,
+        end_line: diagnostic.end_line,
+        end_column: diagnostic.end_column,
+        rule: rule.to_string(),
+    }
+}
+
+/// Builds semantics for `typescript_code` and surfaces in-file findings that
+/// `validate` used to discard: unused local bindings, references to
+/// identifiers that are never declared in the snippet, and duplicate
+/// declarations. Module-resolution errors ("Cannot find ...") stay suppressed
+/// since this validates isolated snippets that legitimately import from
+/// elsewhere.
+#[rustler::nif]
+fn lint(typescript_code: String) -> NifResult<Vec<LintDiagnostic>> {
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path("check.ts")
+        .unwrap_or_else(|_| SourceType::default().with_typescript(true).with_module(true));
+
+    let is_wrapped = !looks_like_statement(&typescript_code, false);
+    let prefix = "type __ValidationType = ";
+    let code_to_validate = if is_wrapped {
+        format!("{}{};", prefix, typescript_code)
+    } else {
+        typescript_code.clone()
+    };
+    let prefix_len = if is_wrapped { prefix.len() } else { 0 };
+    let starts = line_starts(&typescript_code);
+
+    let parser_return = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        Parser::new(&allocator, &code_to_validate, source_type).parse()
+    }));
+    let Ok(parser_return) = parser_return else {
+        return Ok(Vec::new());
+    };
+    if parser_return.panicked {
+        return Ok(Vec::new());
+    }
+
+    let semantic_return = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        SemanticBuilder::new()
+            .with_check_syntax_error(true)
+            .build(&parser_return.program)
+    }));
+    let Ok(semantic_return) = semantic_return else {
+        return Ok(Vec::new());
+    };
+
+    let mut findings = Vec::new();
+
+    // Duplicate declarations and other in-file semantic errors, minus the
+    // module-resolution noise `validate` already suppresses.
+    for error in &semantic_return.diagnostics {
+        let msg = format!("{}", error);
+        if msg.contains("Cannot find") || msg.contains("module") {
+            continue;
+        }
+        let rule = if msg.contains("already") && msg.contains("declared") {
+            "duplicate-declaration"
+        } else {
+            "semantic-error"
+        };
+        for diagnostic in to_diagnostics(std::slice::from_ref(error), &starts, prefix_len) {
+            findings.push(with_rule(diagnostic, rule));
+        }
+    }
+
+    let scoping = semantic_return.semantic.scoping();
+    for symbol_id in scoping.symbol_ids() {
+        if scoping.get_resolved_references(symbol_id).next().is_some() {
+            continue;
+        }
+        let flags = scoping.symbol_flags(symbol_id);
+        if flags.intersects(SymbolFlags::TypeAlias | SymbolFlags::Interface) {
+            // Top-level `type`/`interface` declarations are this crate's
+            // entire input shape (e.g. `type Foo = string`), not dead code —
+            // only flag unused *bindings* (locals, imports), never these.
+            continue;
+        }
+        let span = scoping.symbol_span(symbol_id);
+        if (span.start as usize) < prefix_len {
+            // The synthetic `__ValidationType` alias introduced by the
+            // bare-expression wrapper; never a real unused binding.
+            continue;
+        }
+        let start = (span.start as usize).saturating_sub(prefix_len);
+        let end = (span.end as usize).saturating_sub(prefix_len);
+        let (start_line, start_column) = offset_to_line_col(&starts, start);
+        let (end_line, end_column) = offset_to_line_col(&starts, end);
+        findings.push(LintDiagnostic {
+            message: format!("'{}' is declared but never used", scoping.symbol_name(symbol_id)),
+            severity: "warning".to_string(),
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            rule: "unused-binding".to_string(),
+        });
+    }
+
+    for (name, references) in scoping.root_unresolved_references() {
+        for reference_id in references {
+            let span = semantic_return
+                .semantic
+                .reference_span(scoping.get_reference(*reference_id));
+            let start = (span.start as usize).saturating_sub(prefix_len);
+            let end = (span.end as usize).saturating_sub(prefix_len);
+            let (start_line, start_column) = offset_to_line_col(&starts, start);
+            let (end_line, end_column) = offset_to_line_col(&starts, end);
+            findings.push(LintDiagnostic {
+                message: format!("'{}' is not defined in this snippet", name),
+                severity: "warning".to_string(),
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+                rule: "unresolved-reference".to_string(),
+            });
+        }
+    }
+
+    Ok(findings)
+}
+
+// --- rewrite/3: structural search-and-replace over the type AST ---------
+//
+// Modeled on rust-analyzer's SSR: `$name` in the pattern binds to whatever
+// type subtree occupies that position, and `$name` in the replacement
+// re-emits the bound subtree's source text. Matching is scoped to `TSType`
+// nodes (this crate only ever validates type-level snippets), with a
+// best-effort textual fallback for type constructs we don't unify structurally
+// (object/function/conditional/mapped types) — similar in spirit to SSR's own
+// restriction to a subset of the grammar.
+
+/// A `${name:kind(literal)}` / `${name:not(literal)}` constraint on a
+/// placeholder binding.
+enum PlaceholderConstraint {
+    Kind(String),
+    Not(String),
+}
+
+/// Rewrites `${name:constraint}` placeholders into plain `$name` (which
+/// already parses as an ordinary TS identifier, since `$` is identifier-safe),
+/// recording each constraint separately since oxc has no notion of SSR
+/// metavariables.
+fn extract_placeholder_constraints(template: &str) -> (String, HashMap<String, PlaceholderConstraint>) {
+    let mut output = String::with_capacity(template.len());
+    let mut constraints = HashMap::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("${") {
+        let (before, after_marker) = rest.split_at(start);
+        output.push_str(before);
+        let after_marker = &after_marker[2..];
+
+        let Some(end) = after_marker.find('}') else {
+            // Unterminated `${`; keep it verbatim and stop scanning.
+            output.push_str("${");
+            rest = after_marker;
+            break;
+        };
+
+        let inner = &after_marker[..end];
+        let name = match inner.split_once(':') {
+            Some((name, constraint)) => {
+                let name = name.trim().to_string();
+                let constraint = constraint.trim();
+                if let Some(kind) = constraint.strip_prefix("kind(").and_then(|s| s.strip_suffix(')')) {
+                    constraints.insert(name.clone(), PlaceholderConstraint::Kind(kind.trim().to_string()));
+                } else if let Some(kind) = constraint.strip_prefix("not(").and_then(|s| s.strip_suffix(')')) {
+                    constraints.insert(name.clone(), PlaceholderConstraint::Not(kind.trim().to_string()));
+                }
+                name
+            }
+            None => inner.trim().to_string(),
+        };
+        output.push('$');
+        output.push_str(&name);
+        rest = &after_marker[end + 1..];
+    }
+    output.push_str(rest);
+
+    (output, constraints)
+}
+
+/// Parses a pattern or replacement snippet the same way `validate` wraps a
+/// bare type expression, returning the parsed `TSType` template along with
+/// the wrapped source buffer its spans are relative to.
+fn parse_type_template<'a>(allocator: &'a Allocator, code: &str) -> Option<(TSType<'a>, &'a str)> {
+    let wrapped: &'a str = allocator.alloc_str(&format!("type __SsrTemplate = {};", code));
+    let source_type = SourceType::default().with_typescript(true).with_module(true);
+    let parser_return = Parser::new(allocator, wrapped, source_type).parse();
+    if parser_return.panicked || !parser_return.diagnostics.is_empty() {
+        return None;
+    }
+    match parser_return.program.body.into_iter().next() {
+        Some(Statement::TSTypeAliasDeclaration(decl)) => Some((decl.unbox().type_annotation, wrapped)),
+        _ => None,
+    }
+}
+
+/// A human-readable discriminant for a `TSType`, used by `kind(...)` /
+/// `not(...)` placeholder constraints.
+fn ts_type_kind(ty: &TSType) -> &'static str {
+    match ty {
+        TSType::TSTypeReference(_) => "reference",
+        TSType::TSUnionType(_) => "union",
+        TSType::TSIntersectionType(_) => "intersection",
+        TSType::TSArrayType(_) => "array",
+        TSType::TSTupleType(_) => "tuple",
+        TSType::TSLiteralType(_) => "literal",
+        TSType::TSParenthesizedType(_) => "parenthesized",
+        TSType::TSFunctionType(_) => "function",
+        TSType::TSTypeLiteral(_) => "object",
+        TSType::TSConditionalType(_) => "conditional",
+        TSType::TSMappedType(_) => "mapped",
+        TSType::TSIndexedAccessType(_) => "indexed_access",
+        _ => "other",
+    }
+}
+
+/// Strips redundant parentheses so `(Foo)` unifies the same as `Foo`.
+fn strip_parens<'s, 'a>(mut ty: &'s TSType<'a>) -> &'s TSType<'a> {
+    while let TSType::TSParenthesizedType(inner) = ty {
+        ty = &inner.type_annotation;
+    }
+    ty
+}
+
+/// Attempts to unify `pattern` (whose spans are relative to `pattern_source`,
+/// the wrapped `type __SsrTemplate = ...` buffer) against `target` (whose
+/// spans are relative to `target_source`, the caller's own source), recording
+/// metavariable bindings (as spans into `target_source`) on success. A
+/// placeholder is a bare `TSTypeReference` named `$name` with no type
+/// arguments.
+fn unify(
+    pattern: &TSType,
+    target: &TSType,
+    pattern_source: &str,
+    target_source: &str,
+    constraints: &HashMap<String, PlaceholderConstraint>,
+    bindings: &mut HashMap<String, Span>,
+) -> bool {
+    let pattern = strip_parens(pattern);
+    let target = strip_parens(target);
+
+    if let TSType::TSTypeReference(reference) = pattern {
+        if reference.type_arguments.is_none() {
+            if let TSTypeName::IdentifierReference(id) = &reference.type_name {
+                if let Some(placeholder_name) = id.name.strip_prefix('$') {
+                    if let Some(constraint) = constraints.get(placeholder_name) {
+                        let kind = ts_type_kind(target);
+                        let satisfied = match constraint {
+                            PlaceholderConstraint::Kind(k) => kind == k,
+                            PlaceholderConstraint::Not(k) => kind != k,
+                        };
+                        if !satisfied {
+                            return false;
+                        }
+                    }
+
+                    return match bindings.get(placeholder_name) {
+                        // Same metavariable bound twice (non-linear pattern): the
+                        // two occurrences must cover identical source text.
+                        Some(existing) => {
+                            target_source[existing.start as usize..existing.end as usize]
+                                == target_source[target.span().start as usize..target.span().end as usize]
+                        }
+                        None => {
+                            bindings.insert(placeholder_name.to_string(), target.span());
+                            true
+                        }
+                    };
+                }
+            }
+        }
+    }
+
+    match (pattern, target) {
+        (TSType::TSTypeReference(p), TSType::TSTypeReference(t)) => {
+            let p_name = p.type_name.span();
+            let t_name = t.type_name.span();
+            if pattern_source[p_name.start as usize..p_name.end as usize]
+                != target_source[t_name.start as usize..t_name.end as usize]
+            {
+                return false;
+            }
+            match (&p.type_arguments, &t.type_arguments) {
+                (None, None) => true,
+                (Some(p_args), Some(t_args)) => {
+                    p_args.params.len() == t_args.params.len()
+                        && p_args.params.iter().zip(t_args.params.iter()).all(|(p, t)| {
+                            unify(p, t, pattern_source, target_source, constraints, bindings)
+                        })
+                }
+                _ => false,
+            }
+        }
+        (TSType::TSUnionType(p), TSType::TSUnionType(t)) => {
+            p.types.len() == t.types.len()
+                && p.types.iter().zip(t.types.iter()).all(|(p, t)| {
+                    unify(p, t, pattern_source, target_source, constraints, bindings)
+                })
+        }
+        (TSType::TSIntersectionType(p), TSType::TSIntersectionType(t)) => {
+            p.types.len() == t.types.len()
+                && p.types.iter().zip(t.types.iter()).all(|(p, t)| {
+                    unify(p, t, pattern_source, target_source, constraints, bindings)
+                })
+        }
+        (TSType::TSArrayType(p), TSType::TSArrayType(t)) => unify(
+            &p.element_type,
+            &t.element_type,
+            pattern_source,
+            target_source,
+            constraints,
+            bindings,
+        ),
+        (TSType::TSLiteralType(p), TSType::TSLiteralType(t)) => {
+            pattern_source[p.span().start as usize..p.span().end as usize]
+                == target_source[t.span().start as usize..t.span().end as usize]
+        }
+        _ => {
+            // Node kinds we don't unify structurally: fall back to exact
+            // source text equality (so whitespace differences prevent a match).
+            std::mem::discriminant(pattern) == std::mem::discriminant(target)
+                && pattern_source[pattern.span().start as usize..pattern.span().end as usize]
+                    == target_source[target.span().start as usize..target.span().end as usize]
+        }
+    }
+}
+
+/// Walks a `Program`'s `TSType` nodes, recording every match against
+/// `pattern` (in visit order, nested matches included) along with its
+/// metavariable bindings; `rewrite` drops overlapping matches afterwards.
+struct MatchCollector<'p, 'a> {
+    pattern: &'p TSType<'a>,
+    pattern_source: &'p str,
+    target_source: &'p str,
+    constraints: &'p HashMap<String, PlaceholderConstraint>,
+    matches: Vec<(Span, HashMap<String, Span>)>,
+}
+
+impl<'p, 'a> Visit<'a> for MatchCollector<'p, 'a> {
+    fn visit_ts_type(&mut self, ty: &TSType<'a>) {
+        let mut bindings = HashMap::new();
+        if unify(
+            self.pattern,
+            ty,
+            self.pattern_source,
+            self.target_source,
+            self.constraints,
+            &mut bindings,
+        ) {
+            self.matches.push((ty.span(), bindings));
+        }
+        walk::walk_ts_type(self, ty);
+    }
+}
+
+/// Substitutes each `$name` token in `replacement_template` with the source
+/// text captured for that metavariable, leaving unbound `$name` tokens as-is.
+fn substitute_placeholders(replacement_template: &str, bindings: &HashMap<String, Span>, source: &str) -> String {
+    let mut result = String::with_capacity(replacement_template.len());
+    let mut rest = replacement_template;
+
+    while let Some(idx) = rest.find('$') {
+        let (before, after_dollar) = rest.split_at(idx);
+        result.push_str(before);
+        let after_dollar = &after_dollar[1..];
+        let name_len = after_dollar
+            .char_indices()
+            .take_while(|&(_, c)| c.is_alphanumeric() || c == '_')
+            .count();
+        let name = &after_dollar[..name_len];
+
+        if name.is_empty() {
+            result.push('$');
+        } else if let Some(span) = bindings.get(name) {
+            result.push_str(&source[span.start as usize..span.end as usize]);
+        } else {
+            result.push('$');
+            result.push_str(name);
+        }
+        rest = &after_dollar[name_len..];
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// Structural (AST-level) search-and-replace over a TypeScript/type snippet.
+/// `pattern` and `replacement` use `$name` metavariables that bind to
+/// arbitrary type subtrees; `${name:kind(x)}` / `${name:not(x)}` constrain
+/// what a placeholder is allowed to bind to. Returns the rewritten source, or
+/// the original source unchanged if the pattern doesn't parse or nothing
+/// matches.
+#[rustler::nif]
+fn rewrite(source: String, pattern: String, replacement: String) -> NifResult<String> {
+    let pattern_allocator = Allocator::default();
+    let (pattern_code, constraints) = extract_placeholder_constraints(&pattern);
+    let (replacement_code, _) = extract_placeholder_constraints(&replacement);
+
+    let Some((pattern_type, pattern_source)) = parse_type_template(&pattern_allocator, &pattern_code) else {
+        return Ok(source);
+    };
+
+    // `source`, like the pattern, is commonly a bare type expression (e.g.
+    // `A | B`) rather than a full statement — wrap it the same way
+    // `parse_type_template` wraps the pattern, so it actually parses instead
+    // of silently falling through to "unchanged" below.
+    let is_wrapped = !looks_like_statement(&source, false);
+    let prefix = "type __SsrSource = ";
+    let code_to_validate = if is_wrapped {
+        format!("{}{};", prefix, source)
+    } else {
+        source.clone()
+    };
+
+    let target_allocator = Allocator::default();
+    let source_type = SourceType::default().with_typescript(true).with_module(true);
+    let parser_return = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        Parser::new(&target_allocator, &code_to_validate, source_type).parse()
+    }));
+    let Ok(parser_return) = parser_return else {
+        return Ok(source);
+    };
+    if parser_return.panicked || !parser_return.diagnostics.is_empty() {
+        return Ok(source);
+    }
+
+    let mut collector = MatchCollector {
+        pattern: &pattern_type,
+        pattern_source,
+        target_source: &code_to_validate,
+        constraints: &constraints,
+        matches: Vec::new(),
+    };
+    collector.visit_program(&parser_return.program);
+
+    let mut matches = collector.matches;
+    // Ascending start, descending end: when two matches share a start offset
+    // (e.g. `string[]` and its element type `string`), the wider, outermost
+    // match sorts first so the greedy overlap-skip below prefers it over the
+    // nested one.
+    matches.sort_by_key(|(span, _)| (span.start, std::cmp::Reverse(span.end)));
+
+    let mut edits = Vec::new();
+    let mut last_end = 0u32;
+    for (span, bindings) in matches {
+        if span.start < last_end {
+            // Overlaps the previous match; skip so edits never corrupt
+            // each other.
+            continue;
+        }
+        edits.push((span, substitute_placeholders(&replacement_code, &bindings, &code_to_validate)));
+        last_end = span.end;
+    }
+
+    if edits.is_empty() {
+        return Ok(source);
+    }
+
+    let mut rewritten = String::with_capacity(code_to_validate.len());
+    let mut cursor = 0usize;
+    for (span, replacement_text) in edits {
+        rewritten.push_str(&code_to_validate[cursor..span.start as usize]);
+        rewritten.push_str(&replacement_text);
+        cursor = span.end as usize;
+    }
+    rewritten.push_str(&code_to_validate[cursor..]);
+
+    if is_wrapped {
+        rewritten = rewritten[prefix.len()..rewritten.len() - 1].to_string();
+    }
+
+    Ok(rewritten)
 }
 
 rustler::init!("Elixir.NbTs.Validator");